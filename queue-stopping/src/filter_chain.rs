@@ -0,0 +1,442 @@
+//! RetroArch-style multi-pass post-processing chain applied to the composited
+//! output before it's presented. Each pass renders into its own intermediate
+//! texture (or the swapchain, for the last pass) and reads the previous
+//! pass's output, so shaders can be chained (e.g. an upscaler into a sharpen
+//! pass into a CRT mask) without touching the compositor pipeline itself.
+
+use std::fs;
+use std::path::Path;
+
+use wgpu::util::DeviceExt;
+
+/// How a pass sizes its intermediate render target relative to its input.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScaleMode {
+    /// Multiple of the previous pass's output size.
+    Source(f32),
+    /// Multiple of the final viewport (swapchain) size.
+    Viewport(f32),
+    /// Fixed pixel size, independent of input or viewport.
+    Absolute { width: u32, height: u32 },
+}
+
+/// Sentinel shader path standing in for the embedded identity shader, used
+/// when a preset isn't configured so the composited frame still flows
+/// through the normal pass-based path.
+const BUILTIN_PASSTHROUGH: &str = "__builtin_passthrough__";
+const PASSTHROUGH_SHADER_SOURCE: &str = include_str!("passthrough.wgsl");
+
+#[derive(Debug, Clone)]
+pub struct PassConfig {
+    pub shader_path: std::path::PathBuf,
+    pub scale_mode: ScaleMode,
+    pub filter_mode: wgpu::FilterMode,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FilterPreset {
+    pub passes: Vec<PassConfig>,
+}
+
+/// Per-pass uniforms every ported scaler/CRT/sharpening shader expects.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct PassUniforms {
+    output_size: [f32; 2],
+    source_size: [f32; 2],
+    frame_count: u32,
+    _padding: [u32; 3],
+}
+
+struct Pass {
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    uniform_buffer: wgpu::Buffer,
+    scale_mode: ScaleMode,
+    output_size: (u32, u32),
+    source_size: (u32, u32),
+    // None for the final pass, which targets the swapchain view directly.
+    target: Option<(wgpu::Texture, wgpu::TextureView)>,
+}
+
+pub struct FilterChain {
+    passes: Vec<Pass>,
+    frame_count: u32,
+}
+
+/// Parses a RetroArch-flavored `.slangp`-style preset:
+/// ```text
+/// shaders = 2
+/// shader0 = passes/sharpen.wgsl
+/// scale_type0 = source
+/// scale0 = 1.0
+/// filter_linear0 = true
+/// shader1 = passes/crt.wgsl
+/// scale_type1 = viewport
+/// scale1 = 1.0
+/// ```
+pub fn load_preset(path: &Path) -> std::io::Result<FilterPreset> {
+    let Ok(contents) = fs::read_to_string(path) else {
+        tracing::info!("No filter chain preset at {path:?}, running the identity pass");
+        return Ok(FilterPreset {
+            passes: vec![PassConfig {
+                shader_path: std::path::PathBuf::from(BUILTIN_PASSTHROUGH),
+                scale_mode: ScaleMode::Viewport(1.0),
+                filter_mode: wgpu::FilterMode::Linear,
+            }],
+        });
+    };
+    let mut entries = std::collections::HashMap::new();
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            entries.insert(key.trim().to_string(), value.trim().to_string());
+        }
+    }
+
+    let pass_count: usize = entries
+        .get("shaders")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+
+    let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let mut passes = Vec::with_capacity(pass_count);
+    for i in 0..pass_count {
+        let shader_path = entries
+            .get(&format!("shader{i}"))
+            .map(|p| base_dir.join(p))
+            .unwrap_or_else(|| base_dir.join(format!("pass{i}.wgsl")));
+
+        let scale_type = entries
+            .get(&format!("scale_type{i}"))
+            .map(String::as_str)
+            .unwrap_or("source");
+        let scale = entries
+            .get(&format!("scale{i}"))
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(1.0);
+        let scale_mode = match scale_type {
+            "viewport" => ScaleMode::Viewport(scale),
+            "absolute" => {
+                let width = entries
+                    .get(&format!("scale_x{i}"))
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(1280);
+                let height = entries
+                    .get(&format!("scale_y{i}"))
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(720);
+                ScaleMode::Absolute { width, height }
+            }
+            _ => ScaleMode::Source(scale),
+        };
+
+        let filter_mode = match entries.get(&format!("filter_linear{i}")).map(String::as_str) {
+            Some("false") => wgpu::FilterMode::Nearest,
+            _ => wgpu::FilterMode::Linear,
+        };
+
+        passes.push(PassConfig {
+            shader_path,
+            scale_mode,
+            filter_mode,
+        });
+    }
+
+    if passes.is_empty() {
+        tracing::warn!("Filter chain preset at {path:?} has no passes, running the identity pass");
+        passes.push(PassConfig {
+            shader_path: std::path::PathBuf::from(BUILTIN_PASSTHROUGH),
+            scale_mode: ScaleMode::Viewport(1.0),
+            filter_mode: wgpu::FilterMode::Linear,
+        });
+    }
+
+    Ok(FilterPreset { passes })
+}
+
+fn resolve_size(
+    scale_mode: ScaleMode,
+    source_size: (u32, u32),
+    viewport_size: (u32, u32),
+) -> (u32, u32) {
+    match scale_mode {
+        ScaleMode::Source(scale) => (
+            ((source_size.0 as f32) * scale).round().max(1.0) as u32,
+            ((source_size.1 as f32) * scale).round().max(1.0) as u32,
+        ),
+        ScaleMode::Viewport(scale) => (
+            ((viewport_size.0 as f32) * scale).round().max(1.0) as u32,
+            ((viewport_size.1 as f32) * scale).round().max(1.0) as u32,
+        ),
+        ScaleMode::Absolute { width, height } => (width, height),
+    }
+}
+
+impl FilterChain {
+    pub fn new(
+        device: &wgpu::Device,
+        preset: &FilterPreset,
+        surface_format: wgpu::TextureFormat,
+        viewport_size: (u32, u32),
+    ) -> Self {
+        let mut passes = Vec::with_capacity(preset.passes.len());
+        let mut source_size = viewport_size;
+
+        for (index, pass_config) in preset.passes.iter().enumerate() {
+            let is_last = index == preset.passes.len() - 1;
+            let output_size = resolve_size(pass_config.scale_mode, source_size, viewport_size);
+
+            let shader_source = if pass_config.shader_path == Path::new(BUILTIN_PASSTHROUGH) {
+                PASSTHROUGH_SHADER_SOURCE.to_string()
+            } else {
+                fs::read_to_string(&pass_config.shader_path).unwrap_or_else(|err| {
+                    panic!("Failed to read {:?}: {err}", pass_config.shader_path)
+                })
+            };
+            let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some(&format!("filter_pass_{index}_shader")),
+                source: wgpu::ShaderSource::Wgsl(shader_source.into()),
+            });
+
+            let bind_group_layout =
+                device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                    label: Some(&format!("filter_pass_{index}_bind_group_layout")),
+                    entries: &[
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 0,
+                            visibility: wgpu::ShaderStages::FRAGMENT,
+                            ty: wgpu::BindingType::Texture {
+                                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                                view_dimension: wgpu::TextureViewDimension::D2,
+                                multisampled: false,
+                            },
+                            count: None,
+                        },
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 1,
+                            visibility: wgpu::ShaderStages::FRAGMENT,
+                            ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                            count: None,
+                        },
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 2,
+                            visibility: wgpu::ShaderStages::FRAGMENT,
+                            ty: wgpu::BindingType::Buffer {
+                                ty: wgpu::BufferBindingType::Uniform,
+                                has_dynamic_offset: false,
+                                min_binding_size: None,
+                            },
+                            count: None,
+                        },
+                    ],
+                });
+
+            let pipeline_layout =
+                device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                    label: Some(&format!("filter_pass_{index}_pipeline_layout")),
+                    bind_group_layouts: &[&bind_group_layout],
+                    push_constant_ranges: &[],
+                });
+
+            let target_format = if is_last {
+                surface_format
+            } else {
+                wgpu::TextureFormat::Rgba8Unorm
+            };
+
+            let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(&format!("filter_pass_{index}_render_pipeline")),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: "vs_main",
+                    buffers: &[],
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: "fs_main",
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: target_format,
+                        blend: Some(wgpu::BlendState::REPLACE),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    strip_index_format: None,
+                    front_face: wgpu::FrontFace::Ccw,
+                    cull_mode: None,
+                    ..Default::default()
+                },
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState {
+                    count: 1,
+                    mask: !0,
+                    alpha_to_coverage_enabled: false,
+                },
+                multiview: None,
+            });
+
+            let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+                mag_filter: pass_config.filter_mode,
+                min_filter: pass_config.filter_mode,
+                ..Default::default()
+            });
+
+            let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some(&format!("filter_pass_{index}_uniforms")),
+                contents: bytemuck::bytes_of(&PassUniforms {
+                    output_size: [output_size.0 as f32, output_size.1 as f32],
+                    source_size: [source_size.0 as f32, source_size.1 as f32],
+                    frame_count: 0,
+                    _padding: [0; 3],
+                }),
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            });
+
+            let target = if is_last {
+                None
+            } else {
+                Some(create_intermediate_texture(device, output_size, target_format, index))
+            };
+
+            passes.push(Pass {
+                pipeline: render_pipeline,
+                bind_group_layout,
+                sampler,
+                uniform_buffer,
+                scale_mode: pass_config.scale_mode,
+                output_size,
+                source_size,
+                target,
+            });
+
+            source_size = output_size;
+        }
+
+        Self {
+            passes,
+            frame_count: 0,
+        }
+    }
+
+    /// Reallocates every intermediate texture against the new viewport size,
+    /// called from the `WindowEvent::Resized` handler.
+    pub fn resize(&mut self, device: &wgpu::Device, viewport_size: (u32, u32)) {
+        let mut source_size = viewport_size;
+        for (index, pass) in self.passes.iter_mut().enumerate() {
+            let output_size = resolve_size(pass.scale_mode, source_size, viewport_size);
+            if pass.target.is_some() {
+                let format = wgpu::TextureFormat::Rgba8Unorm;
+                pass.target = Some(create_intermediate_texture(device, output_size, format, index));
+            }
+            pass.source_size = source_size;
+            pass.output_size = output_size;
+            source_size = output_size;
+        }
+    }
+
+    /// Runs every pass in order: pass 0 reads `composited_view`, each
+    /// subsequent pass reads the previous pass's intermediate texture, and
+    /// the last pass writes into `swapchain_view`.
+    pub fn render(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        composited_view: &wgpu::TextureView,
+        swapchain_view: &wgpu::TextureView,
+    ) {
+        self.frame_count = self.frame_count.wrapping_add(1);
+
+        let mut input_view = composited_view;
+        let pass_count = self.passes.len();
+        for (index, pass) in self.passes.iter().enumerate() {
+            let uniforms = PassUniforms {
+                output_size: [pass.output_size.0 as f32, pass.output_size.1 as f32],
+                source_size: [pass.source_size.0 as f32, pass.source_size.1 as f32],
+                frame_count: self.frame_count,
+                _padding: [0; 3],
+            };
+            queue.write_buffer(&pass.uniform_buffer, 0, bytemuck::bytes_of(&uniforms));
+
+            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some(&format!("filter_pass_{index}_bind_group")),
+                layout: &pass.bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(input_view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::Sampler(&pass.sampler),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: pass.uniform_buffer.as_entire_binding(),
+                    },
+                ],
+            });
+
+            let target_view = if index == pass_count - 1 {
+                swapchain_view
+            } else {
+                &pass.target.as_ref().expect("non-final pass has an intermediate target").1
+            };
+
+            {
+                let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some(&format!("filter_pass_{index}")),
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view: target_view,
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                            store: wgpu::StoreOp::Store,
+                        },
+                    })],
+                    depth_stencil_attachment: None,
+                    timestamp_writes: None,
+                    occlusion_query_set: None,
+                });
+                render_pass.set_pipeline(&pass.pipeline);
+                render_pass.set_bind_group(0, &bind_group, &[]);
+                render_pass.draw(0..3, 0..1);
+            }
+
+            if let Some((_, view)) = &pass.target {
+                input_view = view;
+            }
+        }
+    }
+}
+
+fn create_intermediate_texture(
+    device: &wgpu::Device,
+    size: (u32, u32),
+    format: wgpu::TextureFormat,
+    index: usize,
+) -> (wgpu::Texture, wgpu::TextureView) {
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some(&format!("filter_pass_{index}_intermediate")),
+        size: wgpu::Extent3d {
+            width: size.0.max(1),
+            height: size.1.max(1),
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::RENDER_ATTACHMENT,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    (texture, view)
+}
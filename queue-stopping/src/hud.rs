@@ -0,0 +1,89 @@
+//! On-screen text/timecode HUD overlay. Draws diagnostic text (timecode,
+//! present FPS, dropped-frame count, stream IDs) over the composited frame
+//! so operators get an at-a-glance read without an external tool.
+
+use wgpu_glyph::{ab_glyph, GlyphBrush, GlyphBrushBuilder, Section, Text};
+
+/// Embedded so the HUD never depends on a font file being present next to
+/// the binary at runtime.
+const HUD_FONT_BYTES: &[u8] = include_bytes!("assets/Inconsolata-Regular.ttf");
+
+pub struct HudStats {
+    pub input_id: String,
+    pub output_id: String,
+    pub timecode: String,
+    pub present_fps: f32,
+    pub dropped_frames: u64,
+}
+
+pub struct HudState {
+    glyph_brush: GlyphBrush<()>,
+    staging_belt: wgpu::util::StagingBelt,
+    visible: bool,
+}
+
+impl HudState {
+    pub fn new(device: &wgpu::Device, surface_format: wgpu::TextureFormat) -> Self {
+        let font = ab_glyph::FontArc::try_from_slice(HUD_FONT_BYTES).expect("Invalid HUD font file");
+        let glyph_brush = GlyphBrushBuilder::using_font(font).build(device, surface_format);
+        let staging_belt = wgpu::util::StagingBelt::new(1024);
+
+        Self {
+            glyph_brush,
+            staging_belt,
+            visible: true,
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    /// Queues the HUD text and draws it into `view` on top of whatever was
+    /// already rendered there, then recalls the staging belt for next frame.
+    pub fn draw(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        view: &wgpu::TextureView,
+        viewport_size: (u32, u32),
+        stats: &HudStats,
+    ) {
+        if !self.visible {
+            return;
+        }
+
+        let text = format!(
+            "in: {}  out: {}\ntc: {}\nfps: {:.1}  dropped: {}",
+            stats.input_id, stats.output_id, stats.timecode, stats.present_fps, stats.dropped_frames
+        );
+
+        self.glyph_brush.queue(Section {
+            screen_position: (12.0, 12.0),
+            text: vec![Text::new(&text)
+                .with_color([1.0, 1.0, 1.0, 1.0])
+                .with_scale(20.0)],
+            ..Section::default()
+        });
+
+        self.glyph_brush
+            .draw_queued(
+                device,
+                &mut self.staging_belt,
+                encoder,
+                view,
+                viewport_size.0,
+                viewport_size.1,
+            )
+            .expect("Failed to draw HUD text");
+
+        self.staging_belt.finish();
+    }
+
+    /// Must be called once the submitted command buffer has been queued, so
+    /// the belt can reclaim buffers for the next frame.
+    pub fn recall(&mut self) {
+        self.staging_belt.recall();
+    }
+}
@@ -18,15 +18,40 @@ use compositor_render::{
     Frame, FrameData, InputId, OutputId, Resolution,
 };
 use live_compositor::{config::read_config, state::ApiState};
-use std::{path::PathBuf, sync::Arc, thread, time::Duration};
+use std::{
+    path::PathBuf,
+    sync::Arc,
+    thread,
+    time::{Duration, Instant},
+};
 use winit::{
     dpi::PhysicalSize,
     event::{Event, WindowEvent},
     event_loop::{ControlFlow, EventLoop},
+    keyboard::{KeyCode, PhysicalKey},
     window::{Fullscreen, WindowBuilder},
 };
 
+mod filter_chain;
+mod hud;
+mod overlay;
+mod tonemap;
+use filter_chain::FilterChain;
+use hud::{HudStats, HudState};
+use overlay::{Fill, OverlayScene, OverlayState, Rect};
+use tonemap::TonemapState;
+
 const BUNNY_FILE_PATH: &str = "BigBuckBunny.mp4";
+const FILTER_PRESET_FILE: &str = "filters.slangp";
+const HUD_TOGGLE_KEY: KeyCode = KeyCode::KeyH;
+const TONEMAP_OPERATOR_KEY: KeyCode = KeyCode::KeyT;
+const TONEMAP_EXPOSURE_DOWN_KEY: KeyCode = KeyCode::Minus;
+const TONEMAP_EXPOSURE_UP_KEY: KeyCode = KeyCode::Equal;
+const TONEMAP_EXPOSURE_STEP: f32 = 0.1;
+
+/// Nominal source frame rate used to estimate dropped frames from gaps
+/// between consecutive presented PTS values. BigBuckBunny.mp4 is 24fps.
+const ASSUMED_FRAME_RATE: f64 = 24.0;
 
 const OUTPUT_RESOLUTION: Resolution = Resolution {
     width: 1280,
@@ -37,10 +62,29 @@ fn root_dir() -> PathBuf {
     PathBuf::from(env!("CARGO_MANIFEST_DIR"))
 }
 
-struct RenderState {
+struct FormatPipeline {
     render_pipeline: wgpu::RenderPipeline,
     bind_group_layout: wgpu::BindGroupLayout,
+}
+
+/// Composited frames are always blitted into this fixed-size intermediate
+/// texture first; the filter chain then reads from it, so the conversion
+/// pipelines never need to know about the swapchain's format or size.
+///
+/// `Rgba16Float` (rather than an 8-bit format) so HDR sources keep their
+/// extended range through the composite step; the dedicated tonemap pass
+/// brings it back down to SDR before the filter chain runs.
+const COMPOSITED_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+struct RenderState {
+    rgba: FormatPipeline,
+    nv12: FormatPipeline,
+    i420: FormatPipeline,
     sampler: wgpu::Sampler,
+    composited_texture: wgpu::Texture,
+    composited_view: wgpu::TextureView,
+    tonemap: TonemapState,
+    overlay: OverlayState,
 }
 
 fn main() {
@@ -153,10 +197,30 @@ fn main() {
     };
     surface.configure(&wgpu_device, &config);
 
-    let render_state = create_render_pipeline(&wgpu_device, surface_format);
+    let mut render_state = create_render_pipeline(
+        &wgpu_device,
+        OUTPUT_RESOLUTION,
+        surface_format,
+        (size.width, size.height),
+    );
+
+    let filter_preset = filter_chain::load_preset(&root_dir().join(FILTER_PRESET_FILE))
+        .unwrap_or_else(|err| panic!("Failed to load filter chain preset: {err}"));
+    let mut filter_chain = FilterChain::new(
+        &wgpu_device,
+        &filter_preset,
+        surface_format,
+        (size.width, size.height),
+    );
+
+    let mut hud = HudState::new(&wgpu_device, surface_format);
 
     let video_receiver = video.unwrap();
     let mut close_requested = false;
+    let mut dropped_frames: u64 = 0;
+    let mut last_present_at: Option<Instant> = None;
+    let mut last_pts: Option<Duration> = None;
+    let mut present_fps = 0.0_f32;
 
     println!("Running event loop");
     event_loop
@@ -177,16 +241,91 @@ fn main() {
                     config.width = new_size.width;
                     config.height = new_size.height;
                     surface.configure(&wgpu_device, &config);
+                    filter_chain.resize(&wgpu_device, (new_size.width, new_size.height));
+                    render_state
+                        .tonemap
+                        .resize(&wgpu_device, (new_size.width, new_size.height));
+                    render_state
+                        .overlay
+                        .resize(&wgpu_queue, (new_size.width, new_size.height));
+                }
+                Event::WindowEvent {
+                    event:
+                        WindowEvent::KeyboardInput {
+                            event: key_event, ..
+                        },
+                    ..
+                } => {
+                    if key_event.state.is_pressed() {
+                        match key_event.physical_key {
+                            PhysicalKey::Code(HUD_TOGGLE_KEY) => hud.toggle(),
+                            PhysicalKey::Code(TONEMAP_OPERATOR_KEY) => {
+                                render_state.tonemap.cycle_operator()
+                            }
+                            PhysicalKey::Code(TONEMAP_EXPOSURE_DOWN_KEY) => {
+                                render_state.tonemap.adjust_exposure(-TONEMAP_EXPOSURE_STEP)
+                            }
+                            PhysicalKey::Code(TONEMAP_EXPOSURE_UP_KEY) => {
+                                render_state.tonemap.adjust_exposure(TONEMAP_EXPOSURE_STEP)
+                            }
+                            _ => {}
+                        }
+                    }
                 }
                 Event::WindowEvent {
                     event: WindowEvent::RedrawRequested,
                     ..
                 } => {
-                    if let Ok(PipelineEvent::Data(frame)) = video_receiver.try_recv() {
-                        render_texture(&frame, &wgpu_device, &wgpu_queue, &surface, &render_state);
-                        window.request_redraw();
-                        tracing::info!("Received frame");
+                    match video_receiver.try_recv() {
+                        Ok(PipelineEvent::Data(frame)) => {
+                            let now = Instant::now();
+                            if let Some(previous) = last_present_at {
+                                let elapsed = now.duration_since(previous).as_secs_f32();
+                                if elapsed > 0.0 {
+                                    present_fps = 1.0 / elapsed;
+                                }
+                            }
+                            last_present_at = Some(now);
+
+                            // Rough drop estimate: more than one assumed frame
+                            // interval between consecutive PTS means frames
+                            // were skipped upstream of the presenter.
+                            if let Some(previous_pts) = last_pts {
+                                let gap = frame.pts.saturating_sub(previous_pts).as_secs_f64();
+                                let missed = (gap * ASSUMED_FRAME_RATE).round() as i64 - 1;
+                                if missed > 0 {
+                                    dropped_frames += missed as u64;
+                                }
+                            }
+                            last_pts = Some(frame.pts);
+
+                            let stats = HudStats {
+                                input_id: input_id.0.clone(),
+                                output_id: "output_1".to_string(),
+                                timecode: format!("{:.3}s", frame.pts.as_secs_f64()),
+                                present_fps,
+                                dropped_frames,
+                            };
+
+                            let overlay_scene = build_demo_overlay(&stats);
+
+                            render_texture(
+                                &frame,
+                                &wgpu_device,
+                                &wgpu_queue,
+                                &surface,
+                                &render_state,
+                                &mut filter_chain,
+                                &mut hud,
+                                &stats,
+                                &overlay_scene,
+                                (config.width, config.height),
+                            );
+                            tracing::info!("Received frame");
+                        }
+                        Ok(PipelineEvent::EOS) | Err(_) => {}
                     }
+                    window.request_redraw();
                 }
                 Event::AboutToWait => {
                     if close_requested {
@@ -199,54 +338,61 @@ fn main() {
         .unwrap();
 }
 
-fn create_render_pipeline(
+fn texture_entry(binding: u32) -> wgpu::BindGroupLayoutEntry {
+    wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Texture {
+            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+            view_dimension: wgpu::TextureViewDimension::D2,
+            multisampled: false,
+        },
+        count: None,
+    }
+}
+
+fn sampler_entry(binding: u32) -> wgpu::BindGroupLayoutEntry {
+    wgpu::BindGroupLayoutEntry {
+        binding,
+        visibility: wgpu::ShaderStages::FRAGMENT,
+        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+        count: None,
+    }
+}
+
+/// Builds a single-format render pipeline: one full-screen-triangle vertex stage
+/// shared by every format, paired with the fragment entry point and bind group
+/// layout for that plane layout.
+fn create_format_pipeline(
     device: &wgpu::Device,
+    shader: &wgpu::ShaderModule,
     surface_format: wgpu::TextureFormat,
-) -> RenderState {
-    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-        label: Some("Vertex Shader"),
-        source: wgpu::ShaderSource::Wgsl(include_str!("shader.wgsl").into()),
-    });
-
+    label: &str,
+    fs_entry_point: &str,
+    layout_entries: &[wgpu::BindGroupLayoutEntry],
+) -> FormatPipeline {
     let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-        entries: &[
-            wgpu::BindGroupLayoutEntry {
-                binding: 0,
-                visibility: wgpu::ShaderStages::FRAGMENT,
-                ty: wgpu::BindingType::Texture {
-                    sample_type: wgpu::TextureSampleType::Float { filterable: true },
-                    view_dimension: wgpu::TextureViewDimension::D2,
-                    multisampled: false,
-                },
-                count: None,
-            },
-            wgpu::BindGroupLayoutEntry {
-                binding: 1,
-                visibility: wgpu::ShaderStages::FRAGMENT,
-                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
-                count: None,
-            },
-        ],
-        label: Some("texture_bind_group_layout"),
+        entries: layout_entries,
+        label: Some(&format!("{label}_bind_group_layout")),
     });
 
     let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-        label: Some("Render Pipeline Layout"),
+        label: Some(&format!("{label}_pipeline_layout")),
         bind_group_layouts: &[&bind_group_layout],
         push_constant_ranges: &[],
     });
 
     let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-        label: Some("Render Pipeline"),
+        label: Some(&format!("{label}_render_pipeline")),
         layout: Some(&pipeline_layout),
         vertex: wgpu::VertexState {
-            module: &shader,
+            module: shader,
             entry_point: "vs_main",
             buffers: &[], // No vertex buffers as we're using a full-screen triangle
         },
         fragment: Some(wgpu::FragmentState {
-            module: &shader,
-            entry_point: "fs_main",
+            module: shader,
+            entry_point: fs_entry_point,
             targets: &[Some(wgpu::ColorTargetState {
                 format: surface_format,
                 blend: Some(wgpu::BlendState::REPLACE),
@@ -269,12 +415,172 @@ fn create_render_pipeline(
         multiview: None,
     });
 
+    FormatPipeline {
+        render_pipeline,
+        bind_group_layout,
+    }
+}
+
+fn create_render_pipeline(
+    device: &wgpu::Device,
+    resolution: Resolution,
+    surface_format: wgpu::TextureFormat,
+    viewport_size: (u32, u32),
+) -> RenderState {
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("Presenter Shader"),
+        source: wgpu::ShaderSource::Wgsl(include_str!("shader.wgsl").into()),
+    });
+
+    let rgba = create_format_pipeline(
+        device,
+        &shader,
+        COMPOSITED_FORMAT,
+        "rgba",
+        "fs_main",
+        &[texture_entry(0), sampler_entry(1)],
+    );
+
+    let nv12 = create_format_pipeline(
+        device,
+        &shader,
+        COMPOSITED_FORMAT,
+        "nv12",
+        "fs_main_nv12",
+        &[texture_entry(0), texture_entry(1), sampler_entry(2)],
+    );
+
+    let i420 = create_format_pipeline(
+        device,
+        &shader,
+        COMPOSITED_FORMAT,
+        "i420",
+        "fs_main_i420",
+        &[
+            texture_entry(0),
+            texture_entry(1),
+            texture_entry(2),
+            sampler_entry(3),
+        ],
+    );
+
     let sampler = device.create_sampler(&wgpu::SamplerDescriptor::default());
 
+    let composited_texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("composited_texture"),
+        size: wgpu::Extent3d {
+            width: resolution.width as u32,
+            height: resolution.height as u32,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: COMPOSITED_FORMAT,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::RENDER_ATTACHMENT,
+        view_formats: &[],
+    });
+    let composited_view = composited_texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+    let tonemap = TonemapState::new(device, viewport_size);
+    let overlay = OverlayState::new(
+        device,
+        surface_format,
+        (resolution.width as f32, resolution.height as f32),
+        viewport_size,
+    );
+
     RenderState {
-        render_pipeline,
-        bind_group_layout,
+        rgba,
+        nv12,
+        i420,
         sampler,
+        composited_texture,
+        composited_view,
+        tonemap,
+        overlay,
+    }
+}
+
+/// Picks the pipeline and builds the bind group for whatever plane layout the
+/// decoder handed us, so the color conversion happens in the fragment shader
+/// instead of an upstream RGBA copy.
+fn select_format_pipeline<'a>(
+    device: &wgpu::Device,
+    frame: &'a Frame,
+    render_state: &'a RenderState,
+) -> Option<(&'a wgpu::RenderPipeline, wgpu::BindGroup)> {
+    match &frame.data {
+        FrameData::Rgba8UnormWgpuTexture(texture) => {
+            let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                layout: &render_state.rgba.bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(&view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::Sampler(&render_state.sampler),
+                    },
+                ],
+                label: Some("rgba_bind_group"),
+            });
+            Some((&render_state.rgba.render_pipeline, bind_group))
+        }
+        FrameData::Nv12WgpuTexture(luma, chroma) => {
+            let luma_view = luma.create_view(&wgpu::TextureViewDescriptor::default());
+            let chroma_view = chroma.create_view(&wgpu::TextureViewDescriptor::default());
+            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                layout: &render_state.nv12.bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(&luma_view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::TextureView(&chroma_view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: wgpu::BindingResource::Sampler(&render_state.sampler),
+                    },
+                ],
+                label: Some("nv12_bind_group"),
+            });
+            Some((&render_state.nv12.render_pipeline, bind_group))
+        }
+        FrameData::I420WgpuTexture(y, u, v) => {
+            let y_view = y.create_view(&wgpu::TextureViewDescriptor::default());
+            let u_view = u.create_view(&wgpu::TextureViewDescriptor::default());
+            let v_view = v.create_view(&wgpu::TextureViewDescriptor::default());
+            let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                layout: &render_state.i420.bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(&y_view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::TextureView(&u_view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: wgpu::BindingResource::TextureView(&v_view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 3,
+                        resource: wgpu::BindingResource::Sampler(&render_state.sampler),
+                    },
+                ],
+                label: Some("i420_bind_group"),
+            });
+            Some((&render_state.i420.render_pipeline, bind_group))
+        }
+        _ => None,
     }
 }
 
@@ -284,33 +590,22 @@ fn render_texture(
     queue: &Arc<wgpu::Queue>,
     surface: &wgpu::Surface,
     render_state: &RenderState,
+    filter_chain: &mut FilterChain,
+    hud: &mut HudState,
+    hud_stats: &HudStats,
+    overlay_scene: &OverlayScene,
+    viewport_size: (u32, u32),
 ) {
-    let FrameData::Rgba8UnormWgpuTexture(texture) = &frame.data else {
+    let Some((render_pipeline, bind_group)) = select_format_pipeline(device, frame, render_state)
+    else {
         tracing::error!("Unexpected frame data format");
         return;
     };
 
-    let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
-
-    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-        layout: &render_state.bind_group_layout,
-        entries: &[
-            wgpu::BindGroupEntry {
-                binding: 0,
-                resource: wgpu::BindingResource::TextureView(&texture_view),
-            },
-            wgpu::BindGroupEntry {
-                binding: 1,
-                resource: wgpu::BindingResource::Sampler(&render_state.sampler),
-            },
-        ],
-        label: Some("conversion_bind_group"),
-    });
-
-    let frame = surface
+    let surface_texture = surface
         .get_current_texture()
         .expect("Failed to acquire next swap chain texture");
-    let view = frame
+    let swapchain_view = surface_texture
         .texture
         .create_view(&wgpu::TextureViewDescriptor::default());
     let mut command_encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
@@ -320,7 +615,7 @@ fn render_texture(
     {
         let mut render_pass = command_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
             color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                view: &view,
+                view: &render_state.composited_view,
                 resolve_target: None,
                 ops: wgpu::Operations {
                     load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
@@ -328,16 +623,97 @@ fn render_texture(
                 },
             })],
             depth_stencil_attachment: None,
-            label: Some("Render Pass"),
+            label: Some("Composite Pass"),
             timestamp_writes: None,
             occlusion_query_set: None,
         });
 
-        render_pass.set_pipeline(&render_state.render_pipeline);
+        render_pass.set_pipeline(render_pipeline);
         render_pass.set_bind_group(0, &bind_group, &[]);
         render_pass.draw(0..3, 0..1); // Full-screen triangle
     }
 
+    render_state
+        .tonemap
+        .render(device, queue, &mut command_encoder, &render_state.composited_view);
+
+    filter_chain.render(
+        device,
+        queue,
+        &mut command_encoder,
+        render_state.tonemap.view(),
+        &swapchain_view,
+    );
+
+    render_state.overlay.render(
+        device,
+        queue,
+        &mut command_encoder,
+        &swapchain_view,
+        overlay_scene,
+    );
+
+    hud.draw(
+        device,
+        queue,
+        &mut command_encoder,
+        &swapchain_view,
+        viewport_size,
+        hud_stats,
+    );
+
     queue.submit(Some(command_encoder.finish()));
-    frame.present();
+    hud.recall();
+    surface_texture.present();
+}
+
+/// Builds a sample lower-third graphic driven by `stats`, standing in for
+/// whatever a real broadcast-graphics template would generate: a
+/// gradient-filled bar with a rounded, stroked "on air" badge over it. Scene
+/// is rebuilt every frame the same way `HudStats` is.
+fn build_demo_overlay(stats: &HudStats) -> OverlayScene {
+    let mut scene = OverlayScene::new();
+
+    let bar_height = 72.0;
+    let bar = scene.add_rect(
+        Rect {
+            x: 0.0,
+            y: OUTPUT_RESOLUTION.height as f32 - bar_height,
+            width: OUTPUT_RESOLUTION.width as f32,
+            height: bar_height,
+        },
+        0.0,
+        true,
+        None,
+    );
+    scene.set_fill(
+        bar,
+        Fill::LinearGradient {
+            from: [0.0, OUTPUT_RESOLUTION.height as f32 - bar_height],
+            to: [OUTPUT_RESOLUTION.width as f32, OUTPUT_RESOLUTION.height as f32],
+            from_color: [0.05, 0.05, 0.08, 0.85],
+            to_color: [0.05, 0.05, 0.08, 0.0],
+        },
+    );
+
+    let badge = scene.add_rect(
+        Rect {
+            x: 24.0,
+            y: OUTPUT_RESOLUTION.height as f32 - bar_height + 16.0,
+            width: 160.0,
+            height: 40.0,
+        },
+        8.0,
+        true,
+        Some(2.0),
+    );
+    let on_air = stats.dropped_frames == 0;
+    let badge_color = if on_air {
+        [0.8, 0.1, 0.1, 0.9]
+    } else {
+        [0.4, 0.4, 0.4, 0.9]
+    };
+    scene.set_fill(badge, Fill::Solid(badge_color));
+
+    scene
 }
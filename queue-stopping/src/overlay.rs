@@ -0,0 +1,634 @@
+//! CPU-tessellated 2D vector graphics (lower-thirds, shapes, watermarks)
+//! composited over the video after the filter chain runs, independently of
+//! the compositor's own scene graph. An [`OverlayScene`] is a flat list of
+//! shapes described in output-resolution pixel space; callers rebuild it
+//! every frame (add shapes, assign a fill, [`OverlayScene::clear`] and
+//! start over) the same way the HUD rebuilds its text section each frame.
+//!
+//! Fill geometry is triangulated as a simple convex fan, and strokes as a
+//! ribbon of quads along each segment — no mitered joins, which is fine at
+//! broadcast-graphics line widths but will show faceting on sharp corners
+//! at large stroke widths.
+
+use std::f32::consts::PI;
+
+use wgpu::util::DeviceExt;
+
+/// A rectangle in output-resolution pixel space, origin top-left.
+#[derive(Debug, Clone, Copy)]
+pub struct Rect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+/// How a shape is colored. Solid fills are baked into the vertex colors of a
+/// single batched draw call; gradients carry their own per-shape uniform and
+/// draw call since their parameters can't be captured per-vertex alone.
+#[derive(Debug, Clone, Copy)]
+pub enum Fill {
+    Solid([f32; 4]),
+    LinearGradient {
+        from: [f32; 2],
+        to: [f32; 2],
+        from_color: [f32; 4],
+        to_color: [f32; 4],
+    },
+    RadialGradient {
+        center: [f32; 2],
+        radius: f32,
+        inner_color: [f32; 4],
+        outer_color: [f32; 4],
+    },
+}
+
+/// Index into `OverlayScene::shapes`, returned by `add_rect`/`add_path` so
+/// the fill can be assigned afterwards.
+#[derive(Debug, Clone, Copy)]
+pub struct ShapeHandle(usize);
+
+struct Shape {
+    vertices: Vec<[f32; 2]>,
+    indices: Vec<u16>,
+    fill: Fill,
+}
+
+#[derive(Default)]
+pub struct OverlayScene {
+    shapes: Vec<Shape>,
+}
+
+impl OverlayScene {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn clear(&mut self) {
+        self.shapes.clear();
+    }
+
+    /// Adds an (optionally rounded, optionally stroked) filled rectangle.
+    /// Defaults to an opaque white fill; call [`OverlayScene::set_fill`] to
+    /// change it.
+    pub fn add_rect(
+        &mut self,
+        rect: Rect,
+        corner_radius: f32,
+        filled: bool,
+        stroke_width: Option<f32>,
+    ) -> ShapeHandle {
+        let points = rounded_rect_points(rect, corner_radius, 8);
+        self.add_polygon(&points, true, filled, stroke_width)
+    }
+
+    /// Adds a path through `points`. If `closed`, the last point connects
+    /// back to the first for both the fill (which assumes the path is
+    /// convex) and the stroke.
+    pub fn add_path(
+        &mut self,
+        points: &[[f32; 2]],
+        closed: bool,
+        filled: bool,
+        stroke_width: Option<f32>,
+    ) -> ShapeHandle {
+        self.add_polygon(points, closed, filled, stroke_width)
+    }
+
+    fn add_polygon(
+        &mut self,
+        points: &[[f32; 2]],
+        closed: bool,
+        filled: bool,
+        stroke_width: Option<f32>,
+    ) -> ShapeHandle {
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+
+        if filled && points.len() >= 3 {
+            vertices.extend_from_slice(points);
+            indices.extend(fan_indices(points.len(), 0));
+        }
+
+        if let Some(width) = stroke_width {
+            let base = vertices.len() as u16;
+            let (stroke_vertices, stroke_indices) = stroke_ribbon(points, closed, width);
+            vertices.extend(stroke_vertices);
+            indices.extend(stroke_indices.into_iter().map(|i| i + base));
+        }
+
+        self.shapes.push(Shape {
+            vertices,
+            indices,
+            fill: Fill::Solid([1.0, 1.0, 1.0, 1.0]),
+        });
+        ShapeHandle(self.shapes.len() - 1)
+    }
+
+    /// Sets the fill (solid or gradient) of a previously added shape.
+    pub fn set_fill(&mut self, handle: ShapeHandle, fill: Fill) {
+        self.shapes[handle.0].fill = fill;
+    }
+}
+
+/// Builds the perimeter of a rectangle with arc-subdivided rounded corners,
+/// going clockwise from the top-right corner's start. `radius` is clamped to
+/// half the shorter side so it never overshoots into a bowtie.
+fn rounded_rect_points(rect: Rect, radius: f32, segments_per_corner: u32) -> Vec<[f32; 2]> {
+    let radius = radius.max(0.0).min(rect.width / 2.0).min(rect.height / 2.0);
+    if radius < f32::EPSILON {
+        return vec![
+            [rect.x, rect.y],
+            [rect.x + rect.width, rect.y],
+            [rect.x + rect.width, rect.y + rect.height],
+            [rect.x, rect.y + rect.height],
+        ];
+    }
+
+    let corners = [
+        (rect.x + rect.width - radius, rect.y + radius, -PI / 2.0),
+        (rect.x + rect.width - radius, rect.y + rect.height - radius, 0.0),
+        (rect.x + radius, rect.y + rect.height - radius, PI / 2.0),
+        (rect.x + radius, rect.y + radius, PI),
+    ];
+
+    let mut points = Vec::with_capacity(corners.len() * (segments_per_corner as usize + 1));
+    for (cx, cy, start_angle) in corners {
+        for i in 0..=segments_per_corner {
+            let t = i as f32 / segments_per_corner as f32;
+            let angle = start_angle + (PI / 2.0) * t;
+            points.push([cx + radius * angle.cos(), cy + radius * angle.sin()]);
+        }
+    }
+    points
+}
+
+/// Triangle-fan indices for a convex polygon of `point_count` vertices
+/// starting at `index_offset` in the shared vertex buffer.
+fn fan_indices(point_count: usize, index_offset: u16) -> Vec<u16> {
+    let mut indices = Vec::with_capacity((point_count.saturating_sub(2)) * 3);
+    for i in 1..point_count as u16 - 1 {
+        indices.push(index_offset);
+        indices.push(index_offset + i);
+        indices.push(index_offset + i + 1);
+    }
+    indices
+}
+
+/// Builds a ribbon of quads (two triangles each) of `width` centered on each
+/// segment of `points`. Joins are left unmitered: consecutive quads simply
+/// overlap at corners, which is invisible for opaque strokes.
+fn stroke_ribbon(points: &[[f32; 2]], closed: bool, width: f32) -> (Vec<[f32; 2]>, Vec<u16>) {
+    let half = width / 2.0;
+    let n = points.len();
+    let segment_count = if closed { n } else { n.saturating_sub(1) };
+
+    let mut vertices = Vec::with_capacity(segment_count * 4);
+    let mut indices = Vec::with_capacity(segment_count * 6);
+
+    for seg in 0..segment_count {
+        let a = points[seg];
+        let b = points[(seg + 1) % n];
+        let dx = b[0] - a[0];
+        let dy = b[1] - a[1];
+        let len = (dx * dx + dy * dy).sqrt().max(f32::EPSILON);
+        let normal = [-dy / len * half, dx / len * half];
+
+        let base = vertices.len() as u16;
+        vertices.push([a[0] + normal[0], a[1] + normal[1]]);
+        vertices.push([a[0] - normal[0], a[1] - normal[1]]);
+        vertices.push([b[0] + normal[0], b[1] + normal[1]]);
+        vertices.push([b[0] - normal[0], b[1] - normal[1]]);
+        indices.extend_from_slice(&[base, base + 1, base + 2, base + 1, base + 3, base + 2]);
+    }
+
+    (vertices, indices)
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct SolidVertex {
+    position: [f32; 2],
+    color: [f32; 4],
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct GradientVertex {
+    position: [f32; 2],
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct ViewUniforms {
+    /// `1.0 / OUTPUT_RESOLUTION`, to normalize pixel coordinates to `[0, 1]`.
+    inv_output_size: [f32; 2],
+    /// Letterbox scale applied after mapping to NDC, so shapes drawn in
+    /// output-resolution space keep their proportions regardless of the
+    /// window's aspect ratio.
+    scale: [f32; 2],
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct GradientUniforms {
+    // LinearGradient: from/to are endpoints; RadialGradient: from is the
+    // center and to.x is the radius (to.y unused).
+    from: [f32; 2],
+    to: [f32; 2],
+    from_color: [f32; 4],
+    to_color: [f32; 4],
+    /// 0 = linear, 1 = radial.
+    kind: u32,
+    _padding: [u32; 3],
+}
+
+/// Holds the solid-color and gradient pipelines, plus the view transform
+/// shared by both, that draw an [`OverlayScene`] into the swapchain view.
+pub struct OverlayState {
+    solid_pipeline: wgpu::RenderPipeline,
+    solid_bind_group_layout: wgpu::BindGroupLayout,
+    gradient_pipeline: wgpu::RenderPipeline,
+    gradient_bind_group_layout: wgpu::BindGroupLayout,
+    view_uniform_buffer: wgpu::Buffer,
+    output_resolution: (f32, f32),
+}
+
+impl OverlayState {
+    pub fn new(
+        device: &wgpu::Device,
+        surface_format: wgpu::TextureFormat,
+        output_resolution: (f32, f32),
+        viewport_size: (u32, u32),
+    ) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("overlay_shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("overlay.wgsl").into()),
+        });
+
+        let view_uniform_layout_entry = wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+
+        let solid_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("overlay_solid_bind_group_layout"),
+                entries: &[view_uniform_layout_entry],
+            });
+
+        let gradient_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("overlay_gradient_bind_group_layout"),
+                entries: &[
+                    view_uniform_layout_entry,
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    },
+                ],
+            });
+
+        let blend = Some(wgpu::BlendState::ALPHA_BLENDING);
+
+        let solid_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("overlay_solid_pipeline_layout"),
+            bind_group_layouts: &[&solid_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let solid_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("overlay_solid_render_pipeline"),
+            layout: Some(&solid_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_solid",
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: std::mem::size_of::<SolidVertex>() as wgpu::BufferAddress,
+                    step_mode: wgpu::VertexStepMode::Vertex,
+                    attributes: &wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x4],
+                }],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_solid",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: surface_format,
+                    blend,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+        });
+
+        let gradient_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("overlay_gradient_pipeline_layout"),
+                bind_group_layouts: &[&gradient_bind_group_layout],
+                push_constant_ranges: &[],
+            });
+        let gradient_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("overlay_gradient_render_pipeline"),
+            layout: Some(&gradient_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_gradient",
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: std::mem::size_of::<GradientVertex>() as wgpu::BufferAddress,
+                    step_mode: wgpu::VertexStepMode::Vertex,
+                    attributes: &wgpu::vertex_attr_array![0 => Float32x2],
+                }],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_gradient",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: surface_format,
+                    blend,
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+        });
+
+        let view_uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("overlay_view_uniforms"),
+            contents: bytemuck::bytes_of(&view_uniforms(output_resolution, viewport_size)),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        Self {
+            solid_pipeline,
+            solid_bind_group_layout,
+            gradient_pipeline,
+            gradient_bind_group_layout,
+            view_uniform_buffer,
+            output_resolution,
+        }
+    }
+
+    /// Recomputes the letterbox scale for the new window size, called from
+    /// the `WindowEvent::Resized` handler.
+    pub fn resize(&mut self, queue: &wgpu::Queue, viewport_size: (u32, u32)) {
+        queue.write_buffer(
+            &self.view_uniform_buffer,
+            0,
+            bytemuck::bytes_of(&view_uniforms(self.output_resolution, viewport_size)),
+        );
+    }
+
+    /// Draws every shape in `scene` into `target_view` in scene insertion
+    /// order, so shapes added later land on top of earlier ones exactly
+    /// like a normal 2D scene graph. Consecutive solid-fill shapes are
+    /// still batched into a single vertex/index buffer and draw call, but
+    /// that batch is flushed whenever a gradient-fill shape is next so it
+    /// never reorders ahead of or behind one.
+    pub fn render(
+        &self,
+        device: &wgpu::Device,
+        _queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        target_view: &wgpu::TextureView,
+        scene: &OverlayScene,
+    ) {
+        let mut solid_batch: Vec<(&[[f32; 2]], &[u16], [f32; 4])> = Vec::new();
+
+        for shape in &scene.shapes {
+            if shape.indices.is_empty() {
+                continue;
+            }
+            match shape.fill {
+                Fill::Solid(color) => solid_batch.push((&shape.vertices, &shape.indices, color)),
+                gradient => {
+                    self.draw_solid_batch(device, encoder, target_view, &solid_batch);
+                    solid_batch.clear();
+                    self.draw_gradient_shape(
+                        device,
+                        encoder,
+                        target_view,
+                        &shape.vertices,
+                        &shape.indices,
+                        gradient,
+                    );
+                }
+            }
+        }
+
+        self.draw_solid_batch(device, encoder, target_view, &solid_batch);
+    }
+
+    fn draw_solid_batch(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        target_view: &wgpu::TextureView,
+        batch: &[(&[[f32; 2]], &[u16], [f32; 4])],
+    ) {
+        if batch.is_empty() {
+            return;
+        }
+
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        for &(shape_vertices, shape_indices, color) in batch {
+            let base = vertices.len() as u16;
+            vertices.extend(
+                shape_vertices
+                    .iter()
+                    .map(|&position| SolidVertex { position, color }),
+            );
+            indices.extend(shape_indices.iter().map(|i| i + base));
+        }
+
+        let view_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("overlay_solid_bind_group"),
+            layout: &self.solid_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: self.view_uniform_buffer.as_entire_binding(),
+            }],
+        });
+
+        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("overlay_solid_vertex_buffer"),
+            contents: bytemuck::cast_slice(&vertices),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("overlay_solid_index_buffer"),
+            contents: bytemuck::cast_slice(&indices),
+            usage: wgpu::BufferUsages::INDEX,
+        });
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("overlay_solid_pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target_view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        render_pass.set_pipeline(&self.solid_pipeline);
+        render_pass.set_bind_group(0, &view_bind_group, &[]);
+        render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+        render_pass.set_index_buffer(index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+        render_pass.draw_indexed(0..indices.len() as u32, 0, 0..1);
+    }
+
+    fn draw_gradient_shape(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        target_view: &wgpu::TextureView,
+        vertices: &[[f32; 2]],
+        indices: &[u16],
+        fill: Fill,
+    ) {
+        let uniforms = match fill {
+            Fill::LinearGradient {
+                from,
+                to,
+                from_color,
+                to_color,
+            } => GradientUniforms {
+                from,
+                to,
+                from_color,
+                to_color,
+                kind: 0,
+                _padding: [0; 3],
+            },
+            Fill::RadialGradient {
+                center,
+                radius,
+                inner_color,
+                outer_color,
+            } => GradientUniforms {
+                from: center,
+                to: [radius, 0.0],
+                from_color: inner_color,
+                to_color: outer_color,
+                kind: 1,
+                _padding: [0; 3],
+            },
+            Fill::Solid(_) => unreachable!("solid fills are drawn via draw_solid_batch"),
+        };
+        // A fresh buffer per shape, not a shared one refilled via
+        // `write_buffer`: every write in this encoder lands before the GPU
+        // executes any of its draws, so a single shared buffer would have
+        // every gradient draw read back whichever shape wrote last.
+        let gradient_uniform_buffer =
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("overlay_gradient_uniforms"),
+                contents: bytemuck::bytes_of(&uniforms),
+                usage: wgpu::BufferUsages::UNIFORM,
+            });
+
+        let gradient_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("overlay_gradient_bind_group"),
+            layout: &self.gradient_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: self.view_uniform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: gradient_uniform_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        let vertex_data: Vec<GradientVertex> = vertices
+            .iter()
+            .map(|&position| GradientVertex { position })
+            .collect();
+        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("overlay_gradient_vertex_buffer"),
+            contents: bytemuck::cast_slice(&vertex_data),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+        let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("overlay_gradient_index_buffer"),
+            contents: bytemuck::cast_slice(indices),
+            usage: wgpu::BufferUsages::INDEX,
+        });
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("overlay_gradient_pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target_view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        render_pass.set_pipeline(&self.gradient_pipeline);
+        render_pass.set_bind_group(0, &gradient_bind_group, &[]);
+        render_pass.set_vertex_buffer(0, vertex_buffer.slice(..));
+        render_pass.set_index_buffer(index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+        render_pass.draw_indexed(0..indices.len() as u32, 0, 0..1);
+    }
+}
+
+fn view_uniforms(output_resolution: (f32, f32), viewport_size: (u32, u32)) -> ViewUniforms {
+    let output_aspect = output_resolution.0 / output_resolution.1;
+    let viewport_aspect = viewport_size.0 as f32 / viewport_size.1 as f32;
+    let scale = if viewport_aspect > output_aspect {
+        [output_aspect / viewport_aspect, 1.0]
+    } else {
+        [1.0, viewport_aspect / output_aspect]
+    };
+    ViewUniforms {
+        inv_output_size: [1.0 / output_resolution.0, 1.0 / output_resolution.1],
+        scale,
+    }
+}
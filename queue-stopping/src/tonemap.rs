@@ -0,0 +1,269 @@
+//! Tone-maps the HDR composited intermediate (`Rgba16Float`, unclamped) down
+//! to the `Rgba8Unorm` intermediate the filter chain expects, so decoded
+//! frames carrying extended-range highlights aren't crushed by an early
+//! 8-bit blit. Runs as its own pass between the composite draw and the
+//! filter chain, with the operator and exposure adjustable live.
+
+use wgpu::util::DeviceExt;
+
+const OUTPUT_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+
+/// Tone-mapping curve applied before the HDR intermediate is handed to the
+/// rest of the (SDR) presentation pipeline. `None` passes the composited
+/// value straight through and is the default, since only HDR sources need
+/// tone-mapping at all — ordinary SDR content (e.g. BigBuckBunny.mp4) should
+/// present unaltered.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TonemapOperator {
+    /// Bypass: presents the composited value unchanged.
+    None,
+    /// `c / (1 + c)`.
+    Reinhard,
+    /// Narkowicz's ACES filmic approximation.
+    AcesFilmic,
+}
+
+impl TonemapOperator {
+    fn cycle(self) -> Self {
+        match self {
+            TonemapOperator::None => TonemapOperator::Reinhard,
+            TonemapOperator::Reinhard => TonemapOperator::AcesFilmic,
+            TonemapOperator::AcesFilmic => TonemapOperator::None,
+        }
+    }
+
+    fn shader_index(self) -> u32 {
+        match self {
+            TonemapOperator::None => 0,
+            TonemapOperator::Reinhard => 1,
+            TonemapOperator::AcesFilmic => 2,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct TonemapUniforms {
+    exposure: f32,
+    operator: u32,
+    _padding: [u32; 2],
+}
+
+pub struct TonemapState {
+    pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    uniform_buffer: wgpu::Buffer,
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+    operator: TonemapOperator,
+    exposure: f32,
+}
+
+impl TonemapState {
+    pub fn new(device: &wgpu::Device, viewport_size: (u32, u32)) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("tonemap_shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("tonemap.wgsl").into()),
+        });
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("tonemap_bind_group_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("tonemap_pipeline_layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("tonemap_render_pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: OUTPUT_FORMAT,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+        });
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor::default());
+
+        let operator = TonemapOperator::None;
+        let exposure = 1.0;
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("tonemap_uniforms"),
+            contents: bytemuck::bytes_of(&TonemapUniforms {
+                exposure,
+                operator: operator.shader_index(),
+                _padding: [0; 2],
+            }),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let (texture, view) = create_intermediate_texture(device, viewport_size);
+
+        Self {
+            pipeline,
+            bind_group_layout,
+            sampler,
+            uniform_buffer,
+            texture,
+            view,
+            operator,
+            exposure,
+        }
+    }
+
+    /// Reallocates the SDR output texture to match the new window size,
+    /// called from the `WindowEvent::Resized` handler.
+    pub fn resize(&mut self, device: &wgpu::Device, viewport_size: (u32, u32)) {
+        let (texture, view) = create_intermediate_texture(device, viewport_size);
+        self.texture = texture;
+        self.view = view;
+    }
+
+    pub fn cycle_operator(&mut self) {
+        self.operator = self.operator.cycle();
+        tracing::info!("Tonemap operator: {:?}", self.operator);
+    }
+
+    pub fn adjust_exposure(&mut self, delta: f32) {
+        self.exposure = (self.exposure + delta).max(0.01);
+        tracing::info!("Tonemap exposure: {:.2}", self.exposure);
+    }
+
+    /// The tone-mapped SDR view, fed into the filter chain in place of the
+    /// raw composited texture.
+    pub fn view(&self) -> &wgpu::TextureView {
+        &self.view
+    }
+
+    pub fn render(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        hdr_view: &wgpu::TextureView,
+    ) {
+        queue.write_buffer(
+            &self.uniform_buffer,
+            0,
+            bytemuck::bytes_of(&TonemapUniforms {
+                exposure: self.exposure,
+                operator: self.operator.shader_index(),
+                _padding: [0; 2],
+            }),
+        );
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("tonemap_bind_group"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(hdr_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: self.uniform_buffer.as_entire_binding(),
+                },
+            ],
+        });
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("tonemap_pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &self.view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
+
+fn create_intermediate_texture(
+    device: &wgpu::Device,
+    size: (u32, u32),
+) -> (wgpu::Texture, wgpu::TextureView) {
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("tonemap_output_texture"),
+        size: wgpu::Extent3d {
+            width: size.0.max(1),
+            height: size.1.max(1),
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: OUTPUT_FORMAT,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::RENDER_ATTACHMENT,
+        view_formats: &[],
+    });
+    let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    (texture, view)
+}